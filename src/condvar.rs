@@ -0,0 +1,92 @@
+//! A condition variable built on [`SpinlockMutex`], using the Cortex-M event
+//! system to put a waiting core to sleep instead of busy-spinning on a predicate.
+
+use {
+    crate::{
+        hal::sio::{Spinlock, SpinlockValid},
+        mutex::RefMut,
+    },
+    ::cortex_m::asm::{sev, wfe},
+    ::portable_atomic::{AtomicU32, Ordering},
+};
+
+/// A condition variable that pairs with a [`SpinlockMutex`](crate::mutex::SpinlockMutex)'s
+/// [`RefMut`] guard, mirroring the wait/notify model of the kernel `sync` module's
+/// condition variable.
+///
+/// There is no scheduler to park a task on, so `wait` instead drops the caller's
+/// lock and executes `WFE` to sleep the core, while `notify_one`/`notify_all`
+/// execute `SEV` to wake it back up. Because `WFE` can wake spuriously (and a
+/// `SEV` wakes every core listening, not just the one that should act), callers
+/// must still loop on their own predicate around `wait`.
+pub struct CondVar {
+    /// Bumped before every `SEV`, so a waiter can tell a real notification
+    /// apart from a spurious `WFE` wakeup.
+    generation: AtomicU32,
+}
+
+impl CondVar {
+    /// Create a new condition variable.
+    pub const fn new() -> Self {
+        Self {
+            generation: AtomicU32::new(0),
+        }
+    }
+
+    /// Atomically release `guard`'s spinlock and sleep the core until another
+    /// core calls [`notify_one`](Self::notify_one) or [`notify_all`](Self::notify_all),
+    /// then re-claim the lock and return a fresh guard.
+    ///
+    /// `WFE` can wake spuriously, so callers must loop and re-check their
+    /// predicate rather than assuming a single `wait` call means the
+    /// condition holds.
+    pub fn wait<'l, T, const N: usize>(&self, guard: RefMut<'l, T, N>) -> RefMut<'l, T, N>
+    where
+        Spinlock<N>: SpinlockValid,
+    {
+        // Observe the generation *before* dropping the lock, so a notify that
+        // lands between this read and the `wfe` below is not lost.
+        let observed = self.generation.load(Ordering::Acquire);
+        let notify_on_drop = guard.will_notify_on_drop();
+        let data = guard.release();
+        while self.generation.load(Ordering::Acquire) == observed {
+            wfe();
+        }
+        let lock = Spinlock::claim();
+        #[cfg(feature = "debug-lockdep")]
+        crate::lockdep::on_claim::<N>();
+        let fresh = RefMut::new(data, lock);
+        if notify_on_drop {
+            fresh.notify_on_drop()
+        } else {
+            fresh
+        }
+    }
+
+    /// Wake one waiting core.
+    ///
+    /// There is no per-waiter queue on this hardware, so this is equivalent
+    /// to [`notify_all`](Self::notify_all): every core blocked in `wait` will
+    /// observe the new generation and race to re-claim the spinlock.
+    pub fn notify_one(&self) {
+        self.bump_and_wake();
+    }
+
+    /// Wake every waiting core.
+    pub fn notify_all(&self) {
+        self.bump_and_wake();
+    }
+
+    fn bump_and_wake(&self) {
+        // Increment before `SEV` so a waiter that hasn't gone to sleep yet
+        // still observes the new generation instead of missing this notify.
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        sev();
+    }
+}
+
+impl Default for CondVar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
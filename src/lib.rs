@@ -11,11 +11,15 @@ pub(crate) use rp2040_hal as hal;
 #[cfg(not(any(feature = "rp2040-hal", feature = "rp235x-hal")))]
 compile_error!("You must choose a HAL implementation!");
 #[cfg(feature = "core-guards")]
-pub mod core_guard;
+pub mod cores;
 
 #[cfg(feature = "isr-guards")]
 pub mod isr_guard;
 
+pub mod condvar;
+#[cfg(feature = "debug-lockdep")]
+pub(crate) mod lockdep;
+pub mod locked_by;
 pub mod mutex;
 
 pub(crate) mod sealed_trait {
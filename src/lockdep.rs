@@ -0,0 +1,48 @@
+//! Debug-only deadlock and lock-ordering detection for
+//! [`SpinlockMutex`](crate::mutex::SpinlockMutex), gated behind the
+//! `debug-lockdep` feature.
+//!
+//! Borrows the lockdep/lock-class idea from the kernel spinlock abstraction:
+//! each core tracks, as a bitmask, the set of hardware spinlock numbers it
+//! currently holds. A recursive claim on an already-held number panics
+//! instead of deadlocking, and so does claiming a lower-numbered spinlock
+//! while a higher-numbered one is already held on that core, enforcing the
+//! acyclic acquisition order that rules out cross-core AB/BA deadlocks.
+//! Compiles to nothing when the feature is off.
+
+use crate::hal::sio::{CoreId, Sio};
+use ::portable_atomic::{AtomicU32, Ordering};
+
+/// Bitmask of spinlock numbers currently held by core 0.
+static HELD_CORE0: AtomicU32 = AtomicU32::new(0);
+/// Bitmask of spinlock numbers currently held by core 1.
+static HELD_CORE1: AtomicU32 = AtomicU32::new(0);
+
+fn held_on_this_core() -> &'static AtomicU32 {
+    match Sio::core() {
+        CoreId::Core0 => &HELD_CORE0,
+        CoreId::Core1 => &HELD_CORE1,
+    }
+}
+
+/// Record that the current core is about to hold spinlock `N`.
+///
+/// # Panics
+/// Panics if this core already holds `N` (recursive deadlock), or if it
+/// already holds a spinlock numbered higher than `N` (acquisition-order
+/// violation: locks must be claimed in increasing order on a given core).
+pub(crate) fn on_claim<const N: usize>() {
+    let bit = 1u32 << N;
+    let prev = held_on_this_core().fetch_or(bit, Ordering::AcqRel);
+    if prev & bit != 0 {
+        panic!("recursive deadlock on spinlock {N}");
+    }
+    if prev >> N != 0 {
+        panic!("lock ordering violation: spinlock {N} claimed while holding a higher-numbered spinlock");
+    }
+}
+
+/// Record that the current core no longer holds spinlock `N`.
+pub(crate) fn on_release<const N: usize>() {
+    held_on_this_core().fetch_and(!(1u32 << N), Ordering::AcqRel);
+}
@@ -0,0 +1,76 @@
+//! A value whose access is gated by proof that a specific hardware spinlock is held.
+
+use {
+    crate::{
+        hal::sio::{Spinlock, SpinlockValid},
+        mutex::RefMut,
+    },
+    ::core::cell::UnsafeCell,
+};
+
+/// Data stored outside a [`SpinlockMutex`](crate::mutex::SpinlockMutex) but
+/// only accessible while its associated hardware spinlock `N` is held.
+///
+/// Since `Spinlock<N>` is globally unique, a live [`RefMut`] for spinlock `N`
+/// is sufficient proof that no other context holds it, so [`access`](Self::access)
+/// and [`access_mut`](Self::access_mut) can hand out a borrow of `self` for as
+/// long as that guard is borrowed. This lets several disjoint fields share one
+/// lock while keeping the protected data physically separate from the lock
+/// itself, e.g. fields of a struct each wrapped in their own `LockedBy` but all
+/// tied to the same `N`.
+pub struct LockedBy<T, const N: usize>
+where
+    Spinlock<N>: SpinlockValid,
+{
+    data: UnsafeCell<T>,
+}
+
+impl<T, const N: usize> LockedBy<T, N>
+where
+    Spinlock<N>: SpinlockValid,
+{
+    /// Wrap a value, gating its access behind spinlock `N`.
+    pub const fn new(data: T) -> Self {
+        Self {
+            data: UnsafeCell::new(data),
+        }
+    }
+    /// Borrow the inner value, using a live guard for spinlock `N` as proof
+    /// that no other context can be accessing it.
+    pub fn access<'a, U>(&'a self, _guard: &'a RefMut<'_, U, N>) -> &'a T {
+        // SAFETY: The caller holds a `RefMut` for spinlock `N`, and `Spinlock<N>`
+        // is globally unique, so no other context can be holding the same lock
+        // and therefore no other context can be accessing this value.
+        unsafe { &*self.data.get() }
+    }
+    /// Mutably borrow the inner value, using a live guard for spinlock `N` as
+    /// proof that no other context can be accessing it.
+    pub fn access_mut<'a, U>(&'a self, _guard: &'a mut RefMut<'_, U, N>) -> &'a mut T {
+        // SAFETY: See `access`. The caller additionally holds the guard mutably,
+        // so this borrow cannot alias any other live borrow of the same data.
+        unsafe { &mut *self.data.get() }
+    }
+    /// Consume the wrapper, returning the inner value without needing proof
+    /// that the lock is held.
+    pub fn into_inner(self) -> T {
+        // SAFETY: We have exclusive access, since the caller relinquishes ownership.
+        self.data.into_inner()
+    }
+}
+
+// SAFETY: Access is gated by holding spinlock `N`, which only one context can
+// do at a time, so it is safe to share a `LockedBy` across cores.
+unsafe impl<T, const N: usize> Sync for LockedBy<T, N>
+where
+    T: Send,
+    Spinlock<N>: SpinlockValid,
+{
+}
+// SAFETY: `LockedBy` only ever hands out the inner data through a gated borrow,
+// never by value, so sending it between cores is as safe as sending `T` itself.
+unsafe impl<T, const N: usize> Send for LockedBy<T, N>
+where
+    T: Send,
+    Spinlock<N>: SpinlockValid,
+{
+}
@@ -5,17 +5,50 @@ use {
     ::core::{
         cell::UnsafeCell,
         marker::PhantomData,
-        mem::MaybeUninit,
+        mem::{ManuallyDrop, MaybeUninit},
         ops::{Deref, DerefMut},
     },
+    ::portable_atomic::{AtomicBool, AtomicUsize, Ordering},
 };
 
+#[cfg(feature = "core-guards")]
+use crate::cores::SingleCore;
+
+/// A strategy for waiting between failed attempts to claim a contended
+/// spinlock, mirroring the `spin` crate's pluggable relax backends.
+pub trait RelaxStrategy {
+    /// Perform one "relax" step.
+    fn relax();
+}
+
+/// Busy-spin between attempts. This is what [`SpinlockMutex::lock_blocking`]
+/// uses, so callers who need the lowest latency are unaffected by the
+/// existence of [`Wfe`].
+pub struct Spin;
+impl RelaxStrategy for Spin {
+    fn relax() {}
+}
+
+/// Idle the core with `WFE` between attempts, used by
+/// [`SpinlockMutex::lock_blocking_wfe`]. Only reduces power draw if whoever
+/// releases the lock executes `SEV`.
+pub struct Wfe;
+impl RelaxStrategy for Wfe {
+    fn relax() {
+        ::cortex_m::asm::wfe();
+    }
+}
+
 /// A mutex backed by a hardware spinlock.
 pub struct SpinlockMutex<T, const N: usize>
 where
     Spinlock<N>: SpinlockValid,
 {
     data: UnsafeCell<T>,
+    /// Set once the cell holds a valid `T`. Only meaningful for
+    /// `SpinlockMutex<MaybeUninit<T>, N>`, where [`init_once`](Self::init_once)
+    /// relies on it for a one-time initialization guarantee across racing cores.
+    initialized: AtomicBool,
     _marker: PhantomData<Spinlock<N>>,
 }
 
@@ -27,6 +60,7 @@ where
     pub const fn new(data: T) -> Self {
         Self {
             data: UnsafeCell::new(data),
+            initialized: AtomicBool::new(true),
             _marker: PhantomData,
         }
     }
@@ -42,7 +76,19 @@ where
     /// Try to claim the spinlock. If successful, returns a mutable reference.
     /// If unsuccessful, returns `None`. Does not block.
     pub fn try_lock(&self) -> Option<RefMut<'_, T, N>> {
-        Spinlock::try_claim().map(|l| self.lock_with(l))
+        // Record the claim *before* touching hardware: recursion/ordering
+        // violations must panic here even when the hardware claim itself
+        // would never succeed (see `lock_blocking`).
+        #[cfg(feature = "debug-lockdep")]
+        crate::lockdep::on_claim::<N>();
+        match Spinlock::try_claim() {
+            Some(lock) => Some(self.lock_with(lock)),
+            None => {
+                #[cfg(feature = "debug-lockdep")]
+                crate::lockdep::on_release::<N>();
+                None
+            }
+        }
     }
     /// Wait for the spinlock to be unlocked, blocking the
     /// current core.
@@ -55,7 +101,39 @@ where
     /// [`lock_with`](Self::lock_with)
     /// method.
     pub fn lock_blocking(&self) -> RefMut<'_, T, N> {
-        self.lock_with(Spinlock::claim())
+        // Record the claim *before* `Spinlock::claim()`: that call busy-loops
+        // forever on a same-core recursive claim, so checking after it would
+        // never run for exactly the deadlock this method's docs warn about.
+        #[cfg(feature = "debug-lockdep")]
+        crate::lockdep::on_claim::<N>();
+        let lock = Spinlock::claim();
+        self.lock_with(lock)
+    }
+    /// Wait for the spinlock to be unlocked like [`lock_blocking`](Self::lock_blocking),
+    /// retrying [`try_lock`](Self::try_lock) and calling `R::relax()` between
+    /// attempts instead of relying on the HAL's busy-spinning blocking claim.
+    /// This is the generic entry point [`lock_blocking_wfe`](Self::lock_blocking_wfe)
+    /// is built on; pass [`Spin`] for the same busy-spin behavior as
+    /// `lock_blocking`, or a custom [`RelaxStrategy`] of your own.
+    pub fn lock_blocking_with<R: RelaxStrategy>(&self) -> RefMut<'_, T, N> {
+        loop {
+            if let Some(guard) = self.try_lock() {
+                return guard;
+            }
+            R::relax();
+        }
+    }
+    /// Wait for the spinlock like [`lock_blocking`](Self::lock_blocking), but
+    /// idle the core with `WFE` between attempts instead of busy-spinning.
+    ///
+    /// This only reduces power draw if the core holding the lock executes
+    /// `SEV` on release to wake this one back up; the returned guard's drop
+    /// path does so, so it pairs correctly with itself on both sides. Pairing
+    /// it with a plain [`lock_blocking`](Self::lock_blocking)/[`lock_with`](Self::lock_with)
+    /// guard still works, but that guard won't `SEV`, so this core may wait
+    /// for an unrelated event before noticing the lock is free.
+    pub fn lock_blocking_wfe(&self) -> RefMut<'_, T, N> {
+        self.lock_blocking_with::<Wfe>().notify_on_drop()
     }
     /// Consume the mutex, returning the inner data. This neither
     /// claims the spinlock, nor blocks the current core.
@@ -94,6 +172,7 @@ where
     pub const fn uninit() -> Self {
         Self {
             data: UnsafeCell::new(MaybeUninit::uninit()),
+            initialized: AtomicBool::new(false),
             _marker: PhantomData,
         }
     }
@@ -103,6 +182,7 @@ where
         SpinlockMutex {
             // SAFETY: Caller asserts it is initialized
             data: UnsafeCell::new(unsafe { data.assume_init() }),
+            initialized: AtomicBool::new(true),
             _marker: PhantomData,
         }
     }
@@ -133,6 +213,30 @@ where
         let l = self.lock_with(lock);
         unsafe { l.assume_init() }
     }
+    /// Safely bring a statically-declared, uninitialized mutex online.
+    ///
+    /// The first call to reach this observes the "initialized" flag clear,
+    /// writes `f()` into the cell and sets the flag before returning the
+    /// now-initialized guard; every later call, even a racing one from the
+    /// other core, instead observes the flag already set and just locks and
+    /// returns the existing value, so racing cores can't double-initialize.
+    /// `ctx` is only a capability token proving this core may run the
+    /// initializer; it otherwise plays no role.
+    ///
+    /// This replaces the `assume_init*` family's footgun, where a user could
+    /// lock before anyone had written the value, with a fully safe bring-up
+    /// path.
+    #[cfg(feature = "core-guards")]
+    pub fn init_once(&self, _ctx: &impl SingleCore, f: impl FnOnce() -> T) -> RefMut<'_, T, N> {
+        let mut guard = self.lock_blocking();
+        if !self.initialized.load(Ordering::Acquire) {
+            guard.write(f());
+            self.initialized.store(true, Ordering::Release);
+        }
+        // SAFETY: The cell was just written above, or `initialized` was
+        // already set by an earlier call, so either way it holds a valid `T`.
+        unsafe { guard.assume_init() }
+    }
 }
 
 // SAFETY: Spinlocks provide hardware-level synchronization
@@ -144,8 +248,15 @@ pub struct RefMut<'l, T, const N: usize>
 where
     Spinlock<N>: SpinlockValid,
 {
-    spinlock: Spinlock<N>,
+    // Wrapped in `ManuallyDrop` so `teardown` can release the hardware lock
+    // (and, for `notify_on_drop`, `SEV`) at a precise point, rather than
+    // whenever the compiler would otherwise drop this field.
+    spinlock: ManuallyDrop<Spinlock<N>>,
     data: &'l mut T,
+    /// Set by [`notify_on_drop`](Self::notify_on_drop) for guards returned by
+    /// [`lock_blocking_wfe`](SpinlockMutex::lock_blocking_wfe), so `teardown`
+    /// knows to `SEV` after releasing the lock.
+    notify_on_drop: bool,
     /// Removes any Send/Sync auto-impls
     _marker: PhantomData<*const ()>,
 }
@@ -153,13 +264,56 @@ impl<'l, T, const N: usize> RefMut<'l, T, N>
 where
     Spinlock<N>: SpinlockValid,
 {
-    fn new(data: &'l mut T, lock: Spinlock<N>) -> Self {
+    pub(crate) fn new(data: &'l mut T, lock: Spinlock<N>) -> Self {
         Self {
-            spinlock: lock,
+            spinlock: ManuallyDrop::new(lock),
             data,
+            notify_on_drop: false,
             _marker: PhantomData,
         }
     }
+    /// Mark this guard to execute `SEV` after releasing its spinlock, waking
+    /// any core idling on `WFE` for it. Used by
+    /// [`lock_blocking_wfe`](SpinlockMutex::lock_blocking_wfe).
+    pub(crate) fn notify_on_drop(mut self) -> Self {
+        self.notify_on_drop = true;
+        self
+    }
+    /// Whether this guard is marked to execute `SEV` after releasing its
+    /// spinlock. Used by [`CondVar::wait`](crate::condvar::CondVar::wait) to
+    /// carry the flag over to the fresh guard it reconstructs.
+    pub(crate) fn will_notify_on_drop(&self) -> bool {
+        self.notify_on_drop
+    }
+    /// Release the spinlock (and, if requested, `SEV`), exactly once.
+    fn teardown(&mut self) {
+        #[cfg(feature = "debug-lockdep")]
+        crate::lockdep::on_release::<N>();
+        // SAFETY: Called at most once per guard, from `Drop::drop` or `release`.
+        unsafe { ManuallyDrop::drop(&mut self.spinlock) };
+        if self.notify_on_drop {
+            ::cortex_m::asm::sev();
+        }
+    }
+    /// Release the spinlock early, handing back the raw data reference.
+    /// Used by [`CondVar::wait`](crate::condvar::CondVar::wait) to drop the
+    /// lock before sleeping and re-claim it with a fresh guard afterwards.
+    pub(crate) fn release(self) -> &'l mut T {
+        // `Self` implements `Drop`, so it can't be partially moved out of
+        // directly; go through `ManuallyDrop` instead.
+        let mut this = ManuallyDrop::new(self);
+        this.teardown();
+        // SAFETY: `this` is never accessed again after this read.
+        unsafe { ::core::ptr::read(&this.data) }
+    }
+}
+impl<'l, T, const N: usize> Drop for RefMut<'l, T, N>
+where
+    Spinlock<N>: SpinlockValid,
+{
+    fn drop(&mut self) {
+        self.teardown();
+    }
 }
 impl<'l, T, const N: usize> AsMut<T> for RefMut<'l, T, N>
 where
@@ -200,11 +354,180 @@ where
 {
     /// Same safety warnings as [`MaybeUninit::assume_init_mut`]
     pub unsafe fn assume_init(self) -> RefMut<'l, T, N> {
+        let mut this = ManuallyDrop::new(self);
+        let notify_on_drop = this.notify_on_drop;
+        // SAFETY: `this` is never accessed again, so the spinlock isn't dropped twice.
+        let spinlock = unsafe { ::core::ptr::read(&this.spinlock) };
         RefMut {
-            spinlock: self.spinlock,
+            spinlock,
             // SAFETY: Caller asserts this is initialized
-            data: unsafe { self.data.assume_init_mut() },
+            data: unsafe { this.data.assume_init_mut() },
+            notify_on_drop,
             _marker: PhantomData,
         }
     }
 }
+
+/// The writer bit of an [`RwLock`]'s state: set while a writer holds exclusive
+/// access, clear otherwise. The remaining bits are the live reader count.
+const WRITER_BIT: usize = 1 << (usize::BITS - 1);
+
+/// A reader-writer lock backed by a hardware spinlock, analogous to the `spin`
+/// crate's `RwLock`.
+///
+/// Unlike [`SpinlockMutex`], the hardware spinlock `N` is only claimed for the
+/// brief moment needed to transition the lock's state; it does not guard the
+/// whole critical section, so many readers can hold the lock concurrently.
+///
+/// Because that claim is so brief, it goes straight through [`Spinlock::claim`]/
+/// [`Spinlock::try_claim`] rather than through [`SpinlockMutex::lock_blocking`]/
+/// [`try_lock`](SpinlockMutex::try_lock), so it is invisible to the
+/// `debug-lockdep` checker: mixing an `RwLock<_, N>` with a `SpinlockMutex<_, N>`
+/// on the same `N` will not be caught as recursion or an ordering violation.
+pub struct RwLock<T, const N: usize>
+where
+    Spinlock<N>: SpinlockValid,
+{
+    /// Top bit is the writer flag, the rest is the reader count.
+    state: AtomicUsize,
+    data: UnsafeCell<T>,
+}
+
+impl<T, const N: usize> RwLock<T, N>
+where
+    Spinlock<N>: SpinlockValid,
+{
+    /// Create a new, unlocked reader-writer lock.
+    pub const fn new(data: T) -> Self {
+        Self {
+            state: AtomicUsize::new(0),
+            data: UnsafeCell::new(data),
+        }
+    }
+    /// Try to acquire a shared read lock. Fails, without blocking, if a
+    /// writer currently holds the lock.
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T, N>> {
+        let _lock: Spinlock<N> = Spinlock::try_claim()?;
+        let state = self.state.load(Ordering::Acquire);
+        if state & WRITER_BIT != 0 {
+            return None;
+        }
+        self.state.store(state + 1, Ordering::Release);
+        Some(RwLockReadGuard { lock: self })
+    }
+    /// Acquire a shared read lock, blocking the current core until no writer
+    /// holds the lock.
+    pub fn read(&self) -> RwLockReadGuard<'_, T, N> {
+        loop {
+            if let Some(guard) = self.try_read() {
+                return guard;
+            }
+        }
+    }
+    /// Try to acquire exclusive write access. Fails, without blocking, if any
+    /// readers or a writer currently hold the lock.
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T, N>> {
+        let _lock: Spinlock<N> = Spinlock::try_claim()?;
+        if self.state.load(Ordering::Acquire) != 0 {
+            return None;
+        }
+        self.state.store(WRITER_BIT, Ordering::Release);
+        Some(RwLockWriteGuard { lock: self })
+    }
+    /// Acquire exclusive write access, blocking the current core until no
+    /// readers or writer hold the lock.
+    pub fn write(&self) -> RwLockWriteGuard<'_, T, N> {
+        loop {
+            if let Some(guard) = self.try_write() {
+                return guard;
+            }
+        }
+    }
+    /// Consume the lock, returning the inner data. This neither claims the
+    /// spinlock, nor blocks the current core.
+    pub fn into_inner(self) -> T {
+        // SAFETY: We have exclusive access, since the caller relinquishes ownership.
+        self.data.into_inner()
+    }
+}
+
+// SAFETY: Access is arbitrated by the `state` field's CAS transitions under
+// the hardware spinlock, mirroring `SpinlockMutex`. `read()` hands out `&T`
+// to every reading core at once, so `Sync` additionally needs `T: Sync`,
+// mirroring `std::sync::RwLock`.
+unsafe impl<T, const N: usize> Send for RwLock<T, N>
+where
+    T: Send,
+    Spinlock<N>: SpinlockValid,
+{
+}
+unsafe impl<T, const N: usize> Sync for RwLock<T, N>
+where
+    T: Send + Sync,
+    Spinlock<N>: SpinlockValid,
+{
+}
+
+/// A shared read guard for an [`RwLock`].
+pub struct RwLockReadGuard<'l, T, const N: usize>
+where
+    Spinlock<N>: SpinlockValid,
+{
+    lock: &'l RwLock<T, N>,
+}
+impl<'l, T, const N: usize> Deref for RwLockReadGuard<'l, T, N>
+where
+    Spinlock<N>: SpinlockValid,
+{
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: Holding this guard means the writer bit is clear and will
+        // stay clear until we decrement the reader count on drop.
+        unsafe { &*self.lock.data.get() }
+    }
+}
+impl<'l, T, const N: usize> Drop for RwLockReadGuard<'l, T, N>
+where
+    Spinlock<N>: SpinlockValid,
+{
+    fn drop(&mut self) {
+        let _lock: Spinlock<N> = Spinlock::claim();
+        self.lock.state.fetch_sub(1, Ordering::Release);
+    }
+}
+
+/// An exclusive write guard for an [`RwLock`].
+pub struct RwLockWriteGuard<'l, T, const N: usize>
+where
+    Spinlock<N>: SpinlockValid,
+{
+    lock: &'l RwLock<T, N>,
+}
+impl<'l, T, const N: usize> Deref for RwLockWriteGuard<'l, T, N>
+where
+    Spinlock<N>: SpinlockValid,
+{
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: Holding this guard means we are the sole writer.
+        unsafe { &*self.lock.data.get() }
+    }
+}
+impl<'l, T, const N: usize> DerefMut for RwLockWriteGuard<'l, T, N>
+where
+    Spinlock<N>: SpinlockValid,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: Holding this guard means we are the sole writer.
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+impl<'l, T, const N: usize> Drop for RwLockWriteGuard<'l, T, N>
+where
+    Spinlock<N>: SpinlockValid,
+{
+    fn drop(&mut self) {
+        let _lock: Spinlock<N> = Spinlock::claim();
+        self.lock.state.store(0, Ordering::Release);
+    }
+}